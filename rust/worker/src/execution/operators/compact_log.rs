@@ -0,0 +1,279 @@
+use super::materialize_log::{MaterializeLogsInput, MaterializeLogsOperator, MaterializeLogsOutput};
+use super::pull_log::PullLogsOutput;
+use crate::{
+    execution::operator::Operator,
+    log::log::{Log, TruncateError},
+};
+use async_trait::async_trait;
+use uuid::Uuid;
+
+/// The compact logs operator reclaims log storage for a collection once every
+/// consumer has durably indexed past a given point. This borrows the head/tail
+/// garbage-collection model used by circular operation logs: the log only
+/// advances its head once every consumer has read past an entry, so the safe
+/// reclamation point is the minimum offset across all consumers, never further.
+#[derive(Debug)]
+pub struct CompactLogsOperator {
+    client: Box<dyn Log>,
+}
+
+impl CompactLogsOperator {
+    /// Create a new compact logs operator.
+    /// # Parameters
+    /// * `client` - The log client to truncate once compaction completes.
+    pub fn new(client: Box<dyn Log>) -> Box<Self> {
+        Box::new(CompactLogsOperator { client })
+    }
+}
+
+/// The input to the compact logs operator.
+/// # Parameters
+/// * `collection_id` - The collection to compact.
+/// * `logs` - The log entries spanning the to-be-truncated prefix, e.g. the
+///   result of a `PullLogsOperator` run from the collection's current truncation
+///   point. Replayed into the materialized snapshot before anything is reclaimed.
+/// * `consumer_offsets` - The highest offset each consumer (compactor, replica,
+///   ...) has durably indexed. The log is only truncated up to the minimum of
+///   these, so no consumer ever misses an entry it hasn't read yet.
+#[derive(Debug)]
+pub struct CompactLogsInput {
+    collection_id: Uuid,
+    logs: PullLogsOutput,
+    consumer_offsets: Vec<i64>,
+}
+
+impl CompactLogsInput {
+    /// Create a new compact logs input.
+    /// # Parameters
+    /// * `collection_id` - The collection to compact.
+    /// * `logs` - The log entries spanning the to-be-truncated prefix.
+    /// * `consumer_offsets` - The highest offset each consumer has durably indexed.
+    pub fn new(collection_id: Uuid, logs: PullLogsOutput, consumer_offsets: Vec<i64>) -> Self {
+        CompactLogsInput {
+            collection_id,
+            logs,
+            consumer_offsets,
+        }
+    }
+}
+
+/// The output of the compact logs operator.
+/// # Parameters
+/// * `materialized` - The snapshot of the truncated prefix.
+/// * `truncated_offset` - The offset the log was truncated up to. `0` if there
+///   was no safe offset to reclaim.
+#[derive(Debug)]
+pub struct CompactLogsOutput {
+    materialized: MaterializeLogsOutput,
+    truncated_offset: i64,
+}
+
+impl CompactLogsOutput {
+    /// Get the materialized snapshot of the truncated prefix.
+    /// # Returns
+    /// The surviving records and deleted ids.
+    pub fn materialized(&self) -> &MaterializeLogsOutput {
+        &self.materialized
+    }
+
+    /// Get the offset the log was truncated up to.
+    /// # Returns
+    /// The truncated offset, or `0` if nothing was reclaimed.
+    pub fn truncated_offset(&self) -> i64 {
+        self.truncated_offset
+    }
+}
+
+pub type CompactLogsResult = Result<CompactLogsOutput, TruncateError>;
+
+#[async_trait]
+impl Operator<CompactLogsInput, CompactLogsOutput> for CompactLogsOperator {
+    type Error = TruncateError;
+
+    async fn run(&self, input: &CompactLogsInput) -> CompactLogsResult {
+        // Never truncate past the minimum consumer offset. With no consumers
+        // registered, nothing is safe to reclaim yet.
+        let up_to_offset = input
+            .consumer_offsets
+            .iter()
+            .copied()
+            .min()
+            .map(|min_offset| min_offset.min(input.logs.next_offset()))
+            .unwrap_or(0);
+
+        // Replay only the prefix actually being truncated into the materialized
+        // state, not the whole pulled range, so the snapshot matches what's about
+        // to be reclaimed rather than everything this pull happened to read.
+        let prefix_len = (up_to_offset - input.logs.start_offset()).max(0) as usize;
+        let prefix_len = prefix_len.min(input.logs.logs().len());
+        let truncated_prefix = PullLogsOutput::new(
+            input.logs.logs()[..prefix_len].to_vec(),
+            input.logs.start_offset(),
+            up_to_offset,
+            input.logs.logs()[..prefix_len]
+                .last()
+                .map(|record| record.seq_id.clone()),
+        );
+
+        let materialize_operator = MaterializeLogsOperator::new();
+        let materialize_input = MaterializeLogsInput::new(truncated_prefix);
+        let materialized = match materialize_operator.run(&materialize_input).await {
+            Ok(output) => output,
+            Err(never) => match never {},
+        };
+
+        if up_to_offset > 0 {
+            let mut client = self.client.clone();
+            client
+                .truncate(input.collection_id.to_string(), up_to_offset)
+                .await?;
+        }
+
+        Ok(CompactLogsOutput {
+            materialized,
+            truncated_offset: up_to_offset,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::operators::pull_log::{PullLogsInput, PullLogsOperator, RetryConfig};
+    use crate::log::log::{InMemoryLog, LogRecord};
+    use crate::types::{EmbeddingRecord, Operation};
+    use num_bigint::BigInt;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn test_compact_logs_truncates_up_to_minimum_consumer_offset() {
+        let mut log = Box::new(InMemoryLog::new());
+
+        let collection_uuid = Uuid::from_str("00000000-0000-0000-0000-000000000001").unwrap();
+        let collection_id = collection_uuid.to_string();
+        for i in 1..=4 {
+            log.add_log(
+                collection_id.clone(),
+                Box::new(LogRecord {
+                    collection_id: collection_id.clone(),
+                    log_id: i,
+                    log_id_ts: i,
+                    record: Box::new(EmbeddingRecord {
+                        id: format!("embedding_id_{}", i),
+                        seq_id: BigInt::from(i),
+                        embedding: None,
+                        encoding: None,
+                        metadata: None,
+                        operation: Operation::Add,
+                        collection_id: collection_uuid,
+                    }),
+                }),
+            );
+        }
+
+        let pull_operator = PullLogsOperator::new(log.clone(), RetryConfig::default());
+        let pull_input = PullLogsInput::new(collection_uuid, 0, 100, None, None);
+        let pulled = pull_operator.run(&pull_input).await.unwrap();
+
+        let compact_operator = CompactLogsOperator::new(log);
+        // Two consumers: one has indexed past offset 2, the other only offset 1.
+        let compact_input = CompactLogsInput::new(collection_uuid, pulled, vec![2, 1]);
+        let output = compact_operator.run(&compact_input).await.unwrap();
+
+        // The safe reclamation point is the minimum of the consumer offsets, not
+        // the end of the pulled prefix.
+        assert_eq!(output.truncated_offset(), 1);
+        // Only the truncated prefix (offset 0..1, i.e. the first record) is
+        // materialized, not the whole pulled range.
+        assert_eq!(output.materialized().records().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_compact_logs_no_consumers_truncates_nothing() {
+        let mut log = Box::new(InMemoryLog::new());
+
+        let collection_uuid = Uuid::from_str("00000000-0000-0000-0000-000000000002").unwrap();
+        let collection_id = collection_uuid.to_string();
+        log.add_log(
+            collection_id.clone(),
+            Box::new(LogRecord {
+                collection_id: collection_id.clone(),
+                log_id: 1,
+                log_id_ts: 1,
+                record: Box::new(EmbeddingRecord {
+                    id: "embedding_id_1".to_string(),
+                    seq_id: BigInt::from(1),
+                    embedding: None,
+                    encoding: None,
+                    metadata: None,
+                    operation: Operation::Add,
+                    collection_id: collection_uuid,
+                }),
+            }),
+        );
+
+        let pull_operator = PullLogsOperator::new(log.clone(), RetryConfig::default());
+        let pull_input = PullLogsInput::new(collection_uuid, 0, 100, None, None);
+        let pulled = pull_operator.run(&pull_input).await.unwrap();
+
+        let compact_operator = CompactLogsOperator::new(log);
+        let compact_input = CompactLogsInput::new(collection_uuid, pulled, vec![]);
+        let output = compact_operator.run(&compact_input).await.unwrap();
+
+        assert_eq!(output.truncated_offset(), 0);
+        // Nothing is safe to reclaim, so nothing is materialized either.
+        assert_eq!(output.materialized().records().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_compact_logs_materializes_only_truncated_prefix() {
+        let mut log = Box::new(InMemoryLog::new());
+
+        let collection_uuid = Uuid::from_str("00000000-0000-0000-0000-000000000003").unwrap();
+        let collection_id = collection_uuid.to_string();
+        for i in 1..=4 {
+            log.add_log(
+                collection_id.clone(),
+                Box::new(LogRecord {
+                    collection_id: collection_id.clone(),
+                    log_id: i,
+                    log_id_ts: i,
+                    record: Box::new(EmbeddingRecord {
+                        id: format!("embedding_id_{}", i),
+                        seq_id: BigInt::from(i),
+                        embedding: None,
+                        encoding: None,
+                        metadata: None,
+                        operation: Operation::Add,
+                        collection_id: collection_uuid,
+                    }),
+                }),
+            );
+        }
+
+        let pull_operator = PullLogsOperator::new(log.clone(), RetryConfig::default());
+        let pull_input = PullLogsInput::new(collection_uuid, 0, 100, None, None);
+        let pulled = pull_operator.run(&pull_input).await.unwrap();
+
+        let compact_operator = CompactLogsOperator::new(log);
+        // Every consumer has indexed past offset 3, so the first three records
+        // are reclaimed, but the fourth is not.
+        let compact_input = CompactLogsInput::new(collection_uuid, pulled, vec![3]);
+        let output = compact_operator.run(&compact_input).await.unwrap();
+
+        assert_eq!(output.truncated_offset(), 3);
+        assert_eq!(output.materialized().records().len(), 3);
+        assert!(output
+            .materialized()
+            .records()
+            .contains_key("embedding_id_1"));
+        assert!(output
+            .materialized()
+            .records()
+            .contains_key("embedding_id_3"));
+        assert!(!output
+            .materialized()
+            .records()
+            .contains_key("embedding_id_4"));
+    }
+}
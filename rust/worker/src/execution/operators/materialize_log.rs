@@ -0,0 +1,217 @@
+use super::pull_log::PullLogsOutput;
+use crate::{
+    execution::operator::Operator,
+    types::{EmbeddingRecord, Operation},
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+/// The materialize logs operator replays a collection's operation log into its
+/// final per-record state, so callers get a compacted snapshot instead of having
+/// to fold `Add`/`Update`/`Delete` operations themselves.
+#[derive(Debug)]
+pub struct MaterializeLogsOperator {}
+
+impl MaterializeLogsOperator {
+    /// Create a new materialize logs operator.
+    pub fn new() -> Box<Self> {
+        Box::new(MaterializeLogsOperator {})
+    }
+}
+
+/// The input to the materialize logs operator.
+/// # Parameters
+/// * `logs` - The log entries to replay. Replayed in `seq_id` order regardless
+///   of the order they appear in.
+#[derive(Debug)]
+pub struct MaterializeLogsInput {
+    logs: PullLogsOutput,
+}
+
+impl MaterializeLogsInput {
+    /// Create a new materialize logs input.
+    /// # Parameters
+    /// * `logs` - The log entries to replay.
+    pub fn new(logs: PullLogsOutput) -> Self {
+        MaterializeLogsInput { logs }
+    }
+}
+
+/// The output of the materialize logs operator.
+/// # Parameters
+/// * `records` - The surviving records, keyed by `EmbeddingRecord.id`.
+/// * `deleted_ids` - The ids that were deleted by the log and have no surviving record.
+#[derive(Debug)]
+pub struct MaterializeLogsOutput {
+    records: HashMap<String, EmbeddingRecord>,
+    deleted_ids: Vec<String>,
+}
+
+impl MaterializeLogsOutput {
+    /// Get the surviving records, keyed by id.
+    /// # Returns
+    /// The materialized records.
+    pub fn records(&self) -> &HashMap<String, EmbeddingRecord> {
+        &self.records
+    }
+
+    /// Get the ids that were deleted by the log.
+    /// # Returns
+    /// The deleted ids.
+    pub fn deleted_ids(&self) -> &Vec<String> {
+        &self.deleted_ids
+    }
+}
+
+/// The materialize logs operator cannot fail: it only replays records that were
+/// already successfully pulled from the log.
+#[derive(Debug, thiserror::Error)]
+pub enum MaterializeLogsError {}
+
+pub type MaterializeLogsResult = Result<MaterializeLogsOutput, MaterializeLogsError>;
+
+#[async_trait]
+impl Operator<MaterializeLogsInput, MaterializeLogsOutput> for MaterializeLogsOperator {
+    type Error = MaterializeLogsError;
+
+    async fn run(&self, input: &MaterializeLogsInput) -> MaterializeLogsResult {
+        let mut logs: Vec<&Box<EmbeddingRecord>> = input.logs.logs().iter().collect();
+        logs.sort_by(|a, b| a.seq_id.cmp(&b.seq_id));
+
+        let mut records: HashMap<String, EmbeddingRecord> = HashMap::new();
+        let mut deleted_ids: Vec<String> = Vec::new();
+
+        for record in logs {
+            match &record.operation {
+                Operation::Add => {
+                    deleted_ids.retain(|id| id != &record.id);
+                    records.insert(record.id.clone(), (**record).clone());
+                }
+                Operation::Update => {
+                    deleted_ids.retain(|id| id != &record.id);
+                    match records.get_mut(&record.id) {
+                        Some(existing) => {
+                            if record.embedding.is_some() {
+                                existing.embedding = record.embedding.clone();
+                            }
+                            if record.encoding.is_some() {
+                                existing.encoding = record.encoding.clone();
+                            }
+                            if record.metadata.is_some() {
+                                existing.metadata = record.metadata.clone();
+                            }
+                            // The record's own seq_id always advances to the update
+                            // that touched it last, so callers can tell how current
+                            // a materialized record is.
+                            existing.seq_id = record.seq_id.clone();
+                            existing.operation = record.operation.clone();
+                        }
+                        // An update for an id that was never added is treated as an insert.
+                        None => {
+                            records.insert(record.id.clone(), (**record).clone());
+                        }
+                    }
+                }
+                Operation::Delete => {
+                    records.remove(&record.id);
+                    deleted_ids.push(record.id.clone());
+                }
+            }
+        }
+
+        Ok(MaterializeLogsOutput {
+            records,
+            deleted_ids,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::operators::pull_log::PullLogsOutput;
+    use num_bigint::BigInt;
+    use uuid::Uuid;
+
+    fn record(
+        id: &str,
+        seq_id: i64,
+        operation: Operation,
+        embedding: Option<Vec<f32>>,
+    ) -> Box<EmbeddingRecord> {
+        Box::new(EmbeddingRecord {
+            id: id.to_string(),
+            seq_id: BigInt::from(seq_id),
+            embedding,
+            encoding: None,
+            metadata: None,
+            operation,
+            collection_id: Uuid::from_u128(1),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_materialize_logs_add_update_delete() {
+        let logs = PullLogsOutput::new(
+            vec![
+                record("id_1", 1, Operation::Add, Some(vec![1.0])),
+                record("id_2", 2, Operation::Add, Some(vec![2.0])),
+                record("id_1", 3, Operation::Update, Some(vec![1.5])),
+                record("id_2", 4, Operation::Delete, None),
+                // An update for an id that was never added is an insert.
+                record("id_3", 5, Operation::Update, Some(vec![3.0])),
+                // A delete followed by a later add for the same id resurrects it.
+                record("id_2", 6, Operation::Add, Some(vec![2.5])),
+            ],
+            0,
+            7,
+            Some(BigInt::from(6)),
+        );
+
+        let operator = MaterializeLogsOperator::new();
+        let input = MaterializeLogsInput::new(logs);
+        let output = operator.run(&input).await.unwrap();
+
+        assert_eq!(output.records().len(), 3);
+        assert_eq!(
+            output.records().get("id_1").unwrap().embedding,
+            Some(vec![1.5])
+        );
+        assert_eq!(
+            output.records().get("id_2").unwrap().embedding,
+            Some(vec![2.5])
+        );
+        assert_eq!(
+            output.records().get("id_3").unwrap().embedding,
+            Some(vec![3.0])
+        );
+        // The surviving id_1 record reflects the update's seq_id, not the
+        // original add's, so callers can tell how current it is.
+        assert_eq!(
+            output.records().get("id_1").unwrap().seq_id,
+            BigInt::from(3)
+        );
+        assert!(output.deleted_ids().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_materialize_logs_out_of_order_input() {
+        // Logs must be replayed in seq_id order, not input order.
+        let logs = PullLogsOutput::new(
+            vec![
+                record("id_1", 2, Operation::Delete, None),
+                record("id_1", 1, Operation::Add, Some(vec![1.0])),
+            ],
+            0,
+            2,
+            Some(BigInt::from(2)),
+        );
+
+        let operator = MaterializeLogsOperator::new();
+        let input = MaterializeLogsInput::new(logs);
+        let output = operator.run(&input).await.unwrap();
+
+        assert!(output.records().is_empty());
+        assert_eq!(output.deleted_ids(), &vec!["id_1".to_string()]);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod compact_log;
+pub mod materialize_log;
+pub mod pull_log;
@@ -4,20 +4,174 @@ use crate::{
     types::EmbeddingRecord,
 };
 use async_trait::async_trait;
+use futures::{stream, Stream, TryStreamExt};
+use num_bigint::BigInt;
+use std::time::Duration;
 use uuid::Uuid;
 
+/// Backoff settings for retrying a failed `read`.
+/// # Parameters
+/// * `max_retries` - The maximum number of retries before giving up and returning the error.
+/// * `initial_backoff` - The backoff duration before the first retry.
+/// * `max_backoff` - The backoff duration is capped at this value.
+/// * `multiplier` - The factor the backoff grows by after each retry.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: f64,
+}
+
+impl RetryConfig {
+    /// Create a new retry config.
+    /// # Parameters
+    /// * `max_retries` - The maximum number of retries before giving up and returning the error.
+    /// * `initial_backoff` - The backoff duration before the first retry.
+    /// * `max_backoff` - The backoff duration is capped at this value.
+    /// * `multiplier` - The factor the backoff grows by after each retry.
+    pub fn new(
+        max_retries: u32,
+        initial_backoff: Duration,
+        max_backoff: Duration,
+        multiplier: f64,
+    ) -> Self {
+        RetryConfig {
+            max_retries,
+            initial_backoff,
+            max_backoff,
+            multiplier,
+        }
+    }
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            multiplier: 2.0,
+        }
+    }
+}
+
 /// The pull logs operator is responsible for reading logs from the log service.
 #[derive(Debug)]
 pub struct PullLogsOperator {
     client: Box<dyn Log>,
+    retry_config: RetryConfig,
 }
 
 impl PullLogsOperator {
     /// Create a new pull logs operator.
     /// # Parameters
     /// * `client` - The log client to use for reading logs.
-    pub fn new(client: Box<dyn Log>) -> Box<Self> {
-        Box::new(PullLogsOperator { client })
+    /// * `retry_config` - How to retry a `read` that fails with a transient error.
+    pub fn new(client: Box<dyn Log>, retry_config: RetryConfig) -> Box<Self> {
+        Box::new(PullLogsOperator {
+            client,
+            retry_config,
+        })
+    }
+
+    /// Pull the logs for the given input, yielding one batch at a time instead of
+    /// buffering the entire result in memory.
+    /// # Parameters
+    /// * `input` - The input to the pull logs operator.
+    /// # Returns
+    /// A stream of `(batch, next_offset)` pairs, in the same order they would
+    /// appear in the buffered `Vec` returned by `Operator::run`. `next_offset` is
+    /// the cursor position after that batch, advanced by the number of records
+    /// delivered in that batch (after any `num_records` limit is applied) rather
+    /// than the requested `batch_size`.
+    pub fn run_stream(
+        &self,
+        input: &PullLogsInput,
+    ) -> impl Stream<Item = Result<(Vec<Box<EmbeddingRecord>>, i64), PullLogsError>> {
+        let client = self.client.clone();
+        let collection_id = input.collection_id;
+        let batch_size = input.batch_size;
+        let num_records = input.num_records;
+        let end_timestamp = input.end_timestamp;
+        let retry_config = self.retry_config;
+
+        stream::try_unfold(
+            (client, input.offset, 0usize, false),
+            move |(mut client, offset, num_records_read, done)| async move {
+                if done {
+                    return Ok(None);
+                }
+
+                let logs = read_with_retry(
+                    &mut client,
+                    collection_id,
+                    offset,
+                    batch_size,
+                    end_timestamp,
+                    retry_config,
+                )
+                .await?;
+
+                if logs.is_empty() {
+                    return Ok(None);
+                }
+
+                let mut num_records_read = num_records_read + logs.len();
+                let mut logs = logs;
+
+                let done = match num_records {
+                    Some(num_records) if num_records_read >= num_records as usize => {
+                        let num_records = num_records as usize;
+                        if num_records_read > num_records {
+                            logs.truncate(logs.len() - (num_records_read - num_records));
+                        }
+                        num_records_read = num_records;
+                        true
+                    }
+                    _ => false,
+                };
+
+                // Advance by the records actually kept in this batch (after the
+                // `num_records` truncation above), not the raw number read, so the
+                // exposed cursor never skips a record a caller never saw.
+                let offset = offset + logs.len() as i64;
+
+                Ok(Some(((logs, offset), (client, offset, num_records_read, done))))
+            },
+        )
+    }
+}
+
+/// Re-issue a single `read` call, retrying with exponential backoff if it fails.
+/// Only advances once a batch succeeds, so a retry never skips or re-reads logs
+/// beyond the offset it was given.
+async fn read_with_retry(
+    client: &mut Box<dyn Log>,
+    collection_id: Uuid,
+    offset: i64,
+    batch_size: i32,
+    end_timestamp: Option<i64>,
+    retry_config: RetryConfig,
+) -> Result<Vec<Box<EmbeddingRecord>>, PullLogsError> {
+    let mut backoff = retry_config.initial_backoff;
+    let mut retries = 0;
+    loop {
+        match client
+            .read(collection_id.to_string(), offset, batch_size, end_timestamp)
+            .await
+        {
+            Ok(logs) => return Ok(logs),
+            Err(_) if retries < retry_config.max_retries => {
+                retries += 1;
+                tokio::time::sleep(backoff).await;
+                backoff = std::cmp::min(
+                    backoff.mul_f64(retry_config.multiplier),
+                    retry_config.max_backoff,
+                );
+            }
+            Err(e) => return Err(e),
+        }
     }
 }
 
@@ -63,17 +217,41 @@ impl PullLogsInput {
 }
 
 /// The output of the pull logs operator.
-#[derive(Debug)]
+/// # Parameters
+/// * `logs` - The logs that were read.
+/// * `start_offset` - The offset the pull started reading from. Since each
+///   record advances the cursor by exactly one, this lets callers map an
+///   absolute offset back to an index into `logs`.
+/// * `next_offset` - The offset a follow-up pull should start from to resume
+///   exactly where this one left off.
+/// * `last_seq_id` - The `seq_id` of the last log entry read, if any were read.
+#[derive(Debug, Clone)]
 pub struct PullLogsOutput {
     logs: Vec<Box<EmbeddingRecord>>,
+    start_offset: i64,
+    next_offset: i64,
+    last_seq_id: Option<BigInt>,
 }
 
 impl PullLogsOutput {
     /// Create a new pull logs output.
     /// # Parameters
     /// * `logs` - The logs that were read.
-    pub fn new(logs: Vec<Box<EmbeddingRecord>>) -> Self {
-        PullLogsOutput { logs }
+    /// * `start_offset` - The offset the pull started reading from.
+    /// * `next_offset` - The offset a follow-up pull should start from.
+    /// * `last_seq_id` - The `seq_id` of the last log entry read, if any were read.
+    pub fn new(
+        logs: Vec<Box<EmbeddingRecord>>,
+        start_offset: i64,
+        next_offset: i64,
+        last_seq_id: Option<BigInt>,
+    ) -> Self {
+        PullLogsOutput {
+            logs,
+            start_offset,
+            next_offset,
+            last_seq_id,
+        }
     }
 
     /// Get the log entries that were read by an invocation of the pull logs operator.
@@ -82,6 +260,28 @@ impl PullLogsOutput {
     pub fn logs(&self) -> &Vec<Box<EmbeddingRecord>> {
         &self.logs
     }
+
+    /// Get the offset this pull started reading from.
+    /// # Returns
+    /// The start offset.
+    pub fn start_offset(&self) -> i64 {
+        self.start_offset
+    }
+
+    /// Get the offset a follow-up `PullLogsInput` should use to resume exactly
+    /// where this pull left off.
+    /// # Returns
+    /// The next offset.
+    pub fn next_offset(&self) -> i64 {
+        self.next_offset
+    }
+
+    /// Get the `seq_id` of the last log entry read by this pull, if any.
+    /// # Returns
+    /// The last observed `seq_id`, or `None` if no logs were read.
+    pub fn last_seq_id(&self) -> Option<&BigInt> {
+        self.last_seq_id.as_ref()
+    }
 }
 
 pub type PullLogsResult = Result<PullLogsOutput, PullLogsError>;
@@ -91,48 +291,23 @@ impl Operator<PullLogsInput, PullLogsOutput> for PullLogsOperator {
     type Error = PullLogsError;
 
     async fn run(&self, input: &PullLogsInput) -> PullLogsResult {
-        // We expect the log to be cheaply cloneable, we need to clone it since we need
-        // a mutable reference to it. Not necessarily the best, but it works for our needs.
-        let mut client_clone = self.client.clone();
-        let batch_size = input.batch_size;
-        let mut num_records_read = 0;
-        let mut offset = input.offset;
+        // Drive the same batch loop as `run_stream`, buffering every batch into a
+        // single vector for callers that want the whole collection at once.
+        let stream = self.run_stream(input);
+        futures::pin_mut!(stream);
         let mut result = Vec::new();
-        loop {
-            let logs = client_clone
-                .read(
-                    input.collection_id.to_string(),
-                    offset,
-                    batch_size,
-                    input.end_timestamp,
-                )
-                .await;
-
-            let mut logs = match logs {
-                Ok(logs) => logs,
-                Err(e) => {
-                    return Err(e);
-                }
-            };
-
-            if logs.is_empty() {
-                break;
-            }
-
-            num_records_read += logs.len();
-            offset += batch_size as i64;
+        let mut next_offset = input.offset;
+        while let Some((mut logs, offset)) = stream.try_next().await? {
+            next_offset = offset;
             result.append(&mut logs);
-
-            if input.num_records.is_some()
-                && num_records_read >= input.num_records.unwrap() as usize
-            {
-                break;
-            }
-        }
-        if input.num_records.is_some() && result.len() > input.num_records.unwrap() as usize {
-            result.truncate(input.num_records.unwrap() as usize);
         }
-        Ok(PullLogsOutput::new(result))
+        let last_seq_id = result.last().map(|record| record.seq_id.clone());
+        Ok(PullLogsOutput::new(
+            result,
+            input.offset,
+            next_offset,
+            last_seq_id,
+        ))
     }
 }
 
@@ -141,6 +316,7 @@ mod tests {
     use super::*;
     use crate::log::log::InMemoryLog;
     use crate::log::log::LogRecord;
+    use crate::log::log::PullLogsError;
     use crate::types::EmbeddingRecord;
     use crate::types::Operation;
     use num_bigint::BigInt;
@@ -188,7 +364,7 @@ mod tests {
             }),
         );
 
-        let operator = PullLogsOperator::new(log);
+        let operator = PullLogsOperator::new(log, RetryConfig::default());
 
         // Pull all logs from collection 1
         let input = PullLogsInput::new(collection_uuid_1, 0, 1, None, None);
@@ -240,4 +416,175 @@ mod tests {
         let output = operator.run(&input).await.unwrap();
         assert_eq!(output.logs().len(), 1);
     }
+
+    #[tokio::test]
+    async fn test_pull_logs_stream_matches_buffered_run() {
+        let mut log = Box::new(InMemoryLog::new());
+
+        let collection_uuid_1 = Uuid::from_str("00000000-0000-0000-0000-000000000001").unwrap();
+        let collection_id_1 = collection_uuid_1.to_string();
+        for i in 1..=5 {
+            log.add_log(
+                collection_id_1.clone(),
+                Box::new(LogRecord {
+                    collection_id: collection_id_1.clone(),
+                    log_id: i,
+                    log_id_ts: i,
+                    record: Box::new(EmbeddingRecord {
+                        id: format!("embedding_id_{}", i),
+                        seq_id: BigInt::from(i),
+                        embedding: None,
+                        encoding: None,
+                        metadata: None,
+                        operation: Operation::Add,
+                        collection_id: collection_uuid_1,
+                    }),
+                }),
+            );
+        }
+
+        let operator = PullLogsOperator::new(log, RetryConfig::default());
+        let input = PullLogsInput::new(collection_uuid_1, 0, 2, Some(3), None);
+
+        let stream = operator.run_stream(&input);
+        futures::pin_mut!(stream);
+        let mut streamed = Vec::new();
+        while let Some((mut batch, _offset)) = stream.try_next().await.unwrap() {
+            streamed.append(&mut batch);
+        }
+
+        let buffered = operator.run(&input).await.unwrap();
+        assert_eq!(streamed.len(), buffered.logs().len());
+        assert_eq!(streamed.len(), 3);
+        // Offset 3 (not 4): record 4 was read as part of the second batch but
+        // discarded by the `num_records` limit, so it was never delivered and
+        // must remain available to a future pull.
+        assert_eq!(buffered.next_offset(), 3);
+        assert_eq!(buffered.last_seq_id(), Some(&BigInt::from(3)));
+    }
+
+    #[test]
+    fn test_retry_config_default() {
+        let config = RetryConfig::default();
+        assert_eq!(config.max_retries, 3);
+        assert_eq!(config.initial_backoff, std::time::Duration::from_millis(100));
+        assert_eq!(config.max_backoff, std::time::Duration::from_secs(5));
+        assert_eq!(config.multiplier, 2.0);
+    }
+
+    /// A `Log` that fails the first `fails_before_success` reads (or every read,
+    /// if `always_fail` is set) before delegating to an in-memory log, so retry
+    /// behavior can be exercised without a real log service.
+    #[derive(Debug, Clone)]
+    struct FlakyLog {
+        inner: InMemoryLog,
+        fails_before_success: u32,
+        always_fail: bool,
+        attempts: std::sync::Arc<std::sync::atomic::AtomicU32>,
+    }
+
+    impl FlakyLog {
+        fn new(fails_before_success: u32, always_fail: bool) -> Self {
+            FlakyLog {
+                inner: InMemoryLog::new(),
+                fails_before_success,
+                always_fail,
+                attempts: std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0)),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl crate::log::log::Log for FlakyLog {
+        async fn read(
+            &mut self,
+            collection_id: String,
+            offset: i64,
+            batch_size: i32,
+            end_timestamp: Option<i64>,
+        ) -> Result<Vec<Box<EmbeddingRecord>>, PullLogsError> {
+            self.attempts
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if self.always_fail {
+                return Err(PullLogsError::LogReadError);
+            }
+            if self.fails_before_success > 0 {
+                self.fails_before_success -= 1;
+                return Err(PullLogsError::LogReadError);
+            }
+            self.inner
+                .read(collection_id, offset, batch_size, end_timestamp)
+                .await
+        }
+
+        async fn truncate(
+            &mut self,
+            collection_id: String,
+            up_to_offset: i64,
+        ) -> Result<(), crate::log::log::TruncateError> {
+            self.inner.truncate(collection_id, up_to_offset).await
+        }
+
+        fn clone_box(&self) -> Box<dyn crate::log::log::Log> {
+            Box::new(self.clone())
+        }
+    }
+
+    fn small_retry_config(max_retries: u32) -> RetryConfig {
+        RetryConfig::new(
+            max_retries,
+            std::time::Duration::from_millis(1),
+            std::time::Duration::from_millis(5),
+            2.0,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_retry_recovers_from_transient_failures() {
+        let mut flaky = FlakyLog::new(2, false);
+        let collection_uuid = Uuid::from_str("00000000-0000-0000-0000-000000000001").unwrap();
+        let collection_id = collection_uuid.to_string();
+        flaky.inner.add_log(
+            collection_id.clone(),
+            Box::new(LogRecord {
+                collection_id: collection_id.clone(),
+                log_id: 1,
+                log_id_ts: 1,
+                record: Box::new(EmbeddingRecord {
+                    id: "embedding_id_1".to_string(),
+                    seq_id: BigInt::from(1),
+                    embedding: None,
+                    encoding: None,
+                    metadata: None,
+                    operation: Operation::Add,
+                    collection_id: collection_uuid,
+                }),
+            }),
+        );
+        let attempts = flaky.attempts.clone();
+
+        let operator = PullLogsOperator::new(Box::new(flaky), small_retry_config(3));
+        let input = PullLogsInput::new(collection_uuid, 0, 10, None, None);
+        let output = operator.run(&input).await.unwrap();
+
+        assert_eq!(output.logs().len(), 1);
+        // 2 failed reads, then 1 that succeeds, then 1 that observes the log is
+        // exhausted and stops the loop.
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up_after_max_retries() {
+        let flaky = FlakyLog::new(0, true);
+        let attempts = flaky.attempts.clone();
+        let collection_uuid = Uuid::from_str("00000000-0000-0000-0000-000000000002").unwrap();
+
+        let operator = PullLogsOperator::new(Box::new(flaky), small_retry_config(2));
+        let input = PullLogsInput::new(collection_uuid, 0, 10, None, None);
+        let result = operator.run(&input).await;
+
+        assert!(matches!(result, Err(PullLogsError::LogReadError)));
+        // The initial attempt plus 2 retries, all against the same offset.
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
 }
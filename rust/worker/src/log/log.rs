@@ -0,0 +1,251 @@
+use crate::types::EmbeddingRecord;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use thiserror::Error;
+
+/// A single entry in a collection's append-only operation log.
+/// # Parameters
+/// * `collection_id` - The collection the record belongs to.
+/// * `log_id` - The position of this entry in the log.
+/// * `log_id_ts` - The timestamp the entry was written at.
+/// * `record` - The embedding record operation that was logged.
+#[derive(Debug, Clone)]
+pub struct LogRecord {
+    pub collection_id: String,
+    pub log_id: i64,
+    pub log_id_ts: i64,
+    pub record: Box<EmbeddingRecord>,
+}
+
+/// The error type returned by `Log::read`.
+#[derive(Error, Debug, Clone)]
+pub enum PullLogsError {
+    #[error("Error reading logs from the log service")]
+    LogReadError,
+}
+
+/// The error type returned by `Log::truncate`.
+#[derive(Error, Debug, Clone)]
+pub enum TruncateError {
+    #[error("Cannot truncate a collection log past its current length")]
+    OffsetTooLarge,
+    #[error("Cannot truncate a collection log to an offset before its current truncation point")]
+    OffsetAlreadyTruncated,
+}
+
+/// The client used by operators to read from, and reclaim storage for, a
+/// collection's operation log.
+#[async_trait]
+pub trait Log: Debug + Send + Sync {
+    /// Read up to `batch_size` log entries for `collection_id`, starting at `offset`.
+    /// # Parameters
+    /// * `collection_id` - The collection id to read logs from.
+    /// * `offset` - The offset to start reading logs from.
+    /// * `batch_size` - The maximum number of log entries to read.
+    /// * `end_timestamp` - Only return entries written at or before this timestamp.
+    async fn read(
+        &mut self,
+        collection_id: String,
+        offset: i64,
+        batch_size: i32,
+        end_timestamp: Option<i64>,
+    ) -> Result<Vec<Box<EmbeddingRecord>>, PullLogsError>;
+
+    /// Reclaim storage for `collection_id`'s log up to (but not including) `up_to_offset`.
+    /// # Parameters
+    /// * `collection_id` - The collection id to truncate the log for.
+    /// * `up_to_offset` - The offset to truncate the log up to. Entries before this
+    ///   offset are discarded; entries at or after it are kept.
+    async fn truncate(&mut self, collection_id: String, up_to_offset: i64) -> Result<(), TruncateError>;
+
+    /// Clone this log client. Used instead of a `Clone` bound so the client can be
+    /// stored as a `Box<dyn Log>`.
+    fn clone_box(&self) -> Box<dyn Log>;
+}
+
+impl Clone for Box<dyn Log> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// An in-memory `Log` implementation, used in tests.
+///
+/// `logs` is indexed from `0`, but `read`/`truncate` deal in absolute offsets
+/// (the ones callers persist via `PullLogsOutput::next_offset` and resume
+/// from). `truncated_base` tracks how far each collection's log has been
+/// reclaimed, so absolute offsets can be translated to indices into `logs`
+/// even after a truncation has shifted everything down.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryLog {
+    logs: HashMap<String, Vec<Box<LogRecord>>>,
+    truncated_base: HashMap<String, i64>,
+}
+
+impl InMemoryLog {
+    /// Create a new, empty in-memory log.
+    pub fn new() -> Self {
+        InMemoryLog {
+            logs: HashMap::new(),
+            truncated_base: HashMap::new(),
+        }
+    }
+
+    /// Append a log entry for `collection_id`.
+    /// # Parameters
+    /// * `collection_id` - The collection id to append to.
+    /// * `record` - The log entry to append.
+    pub fn add_log(&mut self, collection_id: String, record: Box<LogRecord>) {
+        self.logs.entry(collection_id).or_default().push(record);
+    }
+}
+
+#[async_trait]
+impl Log for InMemoryLog {
+    async fn read(
+        &mut self,
+        collection_id: String,
+        offset: i64,
+        batch_size: i32,
+        end_timestamp: Option<i64>,
+    ) -> Result<Vec<Box<EmbeddingRecord>>, PullLogsError> {
+        let base = self.truncated_base.get(&collection_id).copied().unwrap_or(0);
+        if offset < base {
+            // Already reclaimed by a truncate; nothing to resume from here.
+            return Ok(Vec::new());
+        }
+
+        let logs = match self.logs.get(&collection_id) {
+            Some(logs) => logs,
+            None => return Ok(Vec::new()),
+        };
+
+        let start = (offset - base) as usize;
+        if start >= logs.len() {
+            return Ok(Vec::new());
+        }
+        let end = std::cmp::min(start + batch_size as usize, logs.len());
+
+        let result = logs[start..end]
+            .iter()
+            .filter(|log_record| {
+                end_timestamp
+                    .map(|end_timestamp| log_record.log_id_ts <= end_timestamp)
+                    .unwrap_or(true)
+            })
+            .map(|log_record| log_record.record.clone())
+            .collect();
+        Ok(result)
+    }
+
+    async fn truncate(&mut self, collection_id: String, up_to_offset: i64) -> Result<(), TruncateError> {
+        let base = self.truncated_base.get(&collection_id).copied().unwrap_or(0);
+        if up_to_offset < base {
+            return Err(TruncateError::OffsetAlreadyTruncated);
+        }
+        let up_to = (up_to_offset - base) as usize;
+
+        match self.logs.get_mut(&collection_id) {
+            Some(logs) => {
+                if up_to > logs.len() {
+                    return Err(TruncateError::OffsetTooLarge);
+                }
+                logs.drain(0..up_to);
+            }
+            None if up_to > 0 => return Err(TruncateError::OffsetTooLarge),
+            None => {}
+        }
+
+        self.truncated_base.insert(collection_id, up_to_offset);
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn Log> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Operation;
+    use num_bigint::BigInt;
+    use uuid::Uuid;
+
+    fn log_record(collection_id: &str, log_id: i64, id: &str) -> Box<LogRecord> {
+        Box::new(LogRecord {
+            collection_id: collection_id.to_string(),
+            log_id,
+            log_id_ts: log_id,
+            record: Box::new(EmbeddingRecord {
+                id: id.to_string(),
+                seq_id: BigInt::from(log_id),
+                embedding: None,
+                encoding: None,
+                metadata: None,
+                operation: Operation::Add,
+                collection_id: Uuid::from_u128(1),
+            }),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_truncate_discards_prefix() {
+        let mut log = InMemoryLog::new();
+        for i in 1..=4 {
+            log.add_log("c1".to_string(), log_record("c1", i, &format!("id_{}", i)));
+        }
+
+        log.truncate("c1".to_string(), 2).await.unwrap();
+
+        // Resume from the absolute offset truncate left off at, not from 0.
+        let remaining = log.read("c1".to_string(), 2, 100, None).await.unwrap();
+        assert_eq!(remaining.len(), 2);
+        assert_eq!(remaining[0].id, "id_3");
+        assert_eq!(remaining[1].id, "id_4");
+    }
+
+    #[tokio::test]
+    async fn test_read_resumes_from_absolute_offset_across_truncate() {
+        let mut log = InMemoryLog::new();
+        for i in 1..=4 {
+            log.add_log("c1".to_string(), log_record("c1", i, &format!("id_{}", i)));
+        }
+
+        log.truncate("c1".to_string(), 4).await.unwrap();
+        log.add_log("c1".to_string(), log_record("c1", 5, "id_5"));
+
+        // `next_offset` after the truncation point is 4; resuming from there
+        // must still see records appended afterward.
+        let resumed = log.read("c1".to_string(), 4, 100, None).await.unwrap();
+        assert_eq!(resumed.len(), 1);
+        assert_eq!(resumed[0].id, "id_5");
+
+        // Offsets before the truncation point are gone, not re-served from
+        // whatever now happens to sit at index 0.
+        let reclaimed = log.read("c1".to_string(), 0, 100, None).await.unwrap();
+        assert!(reclaimed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_truncate_before_base_errors() {
+        let mut log = InMemoryLog::new();
+        for i in 1..=4 {
+            log.add_log("c1".to_string(), log_record("c1", i, &format!("id_{}", i)));
+        }
+        log.truncate("c1".to_string(), 2).await.unwrap();
+
+        let result = log.truncate("c1".to_string(), 1).await;
+        assert!(matches!(result, Err(TruncateError::OffsetAlreadyTruncated)));
+    }
+
+    #[tokio::test]
+    async fn test_truncate_past_end_errors() {
+        let mut log = InMemoryLog::new();
+        log.add_log("c1".to_string(), log_record("c1", 1, "id_1"));
+
+        let result = log.truncate("c1".to_string(), 5).await;
+        assert!(matches!(result, Err(TruncateError::OffsetTooLarge)));
+    }
+}